@@ -0,0 +1,145 @@
+//! error numbers shared between the kernel and userspace
+
+use core::fmt;
+
+/// defines the Errno enum along with its numeric values and descriptions from a single table
+macro_rules! errno {
+    ($($(#[$attr:meta])* $name:ident = $num:literal => $description:literal,)*) => {
+        /// an error returned by the kernel. numeric values match those used by Linux on x86
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        #[repr(u32)]
+        pub enum Errno {
+            $($(#[$attr])* $name = $num,)*
+        }
+
+        impl Errno {
+            /// every error, in numeric order
+            #[cfg(test)]
+            const ALL: &'static [Self] = &[$(Self::$name,)*];
+
+            /// gets a human readable description of this error, like strerror() does
+            pub const fn description(self) -> &'static str {
+                match self {
+                    $(Self::$name => $description,)*
+                }
+            }
+        }
+
+        impl TryFrom<u32> for Errno {
+            type Error = ();
+
+            fn try_from(value: u32) -> Result<Self, Self::Error> {
+                match value {
+                    $($num => Ok(Self::$name),)*
+                    _ => Err(()),
+                }
+            }
+        }
+    };
+}
+
+errno! {
+    /// operation not permitted (EPERM)
+    NotPermitted = 1 => "Operation not permitted",
+    /// no such file or directory (ENOENT)
+    NoSuchFileOrDir = 2 => "No such file or directory",
+    /// no such process (ESRCH)
+    NoSuchProcess = 3 => "No such process",
+    /// interrupted system call (EINTR)
+    Interrupted = 4 => "Interrupted system call",
+    /// I/O error (EIO)
+    IOError = 5 => "Input/output error",
+    /// no such device or address (ENXIO)
+    NoSuchDeviceOrAddr = 6 => "No such device or address",
+    /// argument list too long (E2BIG)
+    TooBig = 7 => "Argument list too long",
+    /// exec format error (ENOEXEC)
+    ExecFormatError = 8 => "Exec format error",
+    /// bad file descriptor (EBADF)
+    BadFile = 9 => "Bad file descriptor",
+    /// no child processes (ECHILD)
+    NoChildren = 10 => "No child processes",
+    /// resource temporarily unavailable (EAGAIN)
+    TryAgain = 11 => "Resource temporarily unavailable",
+    /// out of memory (ENOMEM)
+    OutOfMemory = 12 => "Cannot allocate memory",
+    /// permission denied (EACCES)
+    PermissionDenied = 13 => "Permission denied",
+    /// bad address (EFAULT)
+    BadAddress = 14 => "Bad address",
+    /// device or resource busy (EBUSY)
+    Busy = 16 => "Device or resource busy",
+    /// file exists (EEXIST)
+    Exists = 17 => "File exists",
+    /// invalid cross-device link (EXDEV)
+    CrossDeviceLink = 18 => "Invalid cross-device link",
+    /// no such device (ENODEV)
+    NoSuchDevice = 19 => "No such device",
+    /// not a directory (ENOTDIR)
+    NotDirectory = 20 => "Not a directory",
+    /// is a directory (EISDIR)
+    IsDirectory = 21 => "Is a directory",
+    /// invalid argument (EINVAL)
+    InvalidArgument = 22 => "Invalid argument",
+    /// too many open files in system (ENFILE)
+    FileTableOverflow = 23 => "Too many open files in system",
+    /// too many open files (EMFILE)
+    TooManyFiles = 24 => "Too many open files",
+    /// inappropriate ioctl for device (ENOTTY)
+    NotTTY = 25 => "Inappropriate ioctl for device",
+    /// file too large (EFBIG)
+    FileTooBig = 27 => "File too large",
+    /// no space left on device (ENOSPC)
+    NoSpace = 28 => "No space left on device",
+    /// illegal seek (ESPIPE)
+    InvalidSeek = 29 => "Illegal seek",
+    /// read-only file system (EROFS)
+    ReadOnlyFilesystem = 30 => "Read-only file system",
+    /// too many links (EMLINK)
+    TooManyLinks = 31 => "Too many links",
+    /// broken pipe (EPIPE)
+    BrokenPipe = 32 => "Broken pipe",
+    /// numerical result out of range (ERANGE)
+    OutOfRange = 34 => "Numerical result out of range",
+    /// resource deadlock avoided (EDEADLK)
+    Deadlock = 35 => "Resource deadlock avoided",
+    /// file name too long (ENAMETOOLONG)
+    NameTooLong = 36 => "File name too long",
+    /// function not implemented (ENOSYS)
+    NotImplemented = 38 => "Function not implemented",
+    /// directory not empty (ENOTEMPTY)
+    DirNotEmpty = 39 => "Directory not empty",
+    /// too many levels of symbolic links (ELOOP)
+    SymlinkLoop = 40 => "Too many levels of symbolic links",
+    /// link has been severed (ENOLINK)
+    LinkSevered = 67 => "Link has been severed",
+    /// operation not supported (EOPNOTSUPP)
+    NotSupported = 95 => "Operation not supported",
+    /// connection timed out (ETIMEDOUT)
+    TimedOut = 110 => "Connection timed out",
+}
+
+impl fmt::Display for Errno {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for &errno in Errno::ALL {
+            assert_eq!(Errno::try_from(errno as u32), Ok(errno));
+        }
+    }
+
+    #[test]
+    fn unknown() {
+        for num in [0, 15, 26, 37, 41, 111, u32::MAX] {
+            assert_eq!(Errno::try_from(num), Err(()));
+        }
+    }
+}
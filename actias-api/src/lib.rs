@@ -1,2 +1,10 @@
 #![no_std]
-// TODO
+
+pub mod errno;
+pub mod path;
+pub mod time;
+
+/// version of the interface between the kernel and userspace. this must be bumped whenever a change is made that breaks existing binaries
+pub const ABI_VERSION: u32 = 1;
+
+pub use errno::Errno;
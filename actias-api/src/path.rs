@@ -0,0 +1,58 @@
+//! limits on path names
+
+use crate::Errno;
+
+/// maximum length of a path in bytes, including the null terminator
+pub const PATH_MAX: usize = 4096;
+
+/// maximum length of a single component of a path in bytes
+pub const NAME_MAX: usize = 255;
+
+/// checks that a path and all of its components are within PATH_MAX and NAME_MAX, returning Errno::NameTooLong if they aren't
+pub fn check_path_limits(path: &str) -> Result<(), Errno> {
+    if path.len() >= PATH_MAX || path.split('/').any(|component| component.len() > NAME_MAX) {
+        Err(Errno::NameTooLong)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// builds a path made of 100 byte components, so it can reach PATH_MAX without any component reaching NAME_MAX
+    const fn long_path() -> [u8; PATH_MAX] {
+        let mut bytes = [b'a'; PATH_MAX];
+        let mut i = 0;
+        while i < PATH_MAX {
+            bytes[i] = b'/';
+            i += 100;
+        }
+        bytes
+    }
+
+    const LONG_PATH: [u8; PATH_MAX] = long_path();
+    const LONG_NAME: [u8; NAME_MAX + 1] = [b'a'; NAME_MAX + 1];
+
+    fn path(len: usize) -> &'static str {
+        core::str::from_utf8(&LONG_PATH[..len]).unwrap()
+    }
+
+    fn name(len: usize) -> &'static str {
+        core::str::from_utf8(&LONG_NAME[..len]).unwrap()
+    }
+
+    #[test]
+    fn path_length() {
+        // one byte is left over for the null terminator
+        assert_eq!(check_path_limits(path(PATH_MAX - 1)), Ok(()));
+        assert_eq!(check_path_limits(path(PATH_MAX)), Err(Errno::NameTooLong));
+    }
+
+    #[test]
+    fn component_length() {
+        assert_eq!(check_path_limits(name(NAME_MAX)), Ok(()));
+        assert_eq!(check_path_limits(name(NAME_MAX + 1)), Err(Errno::NameTooLong));
+    }
+}
@@ -0,0 +1,124 @@
+//! dates, times, and clocks
+
+use core::fmt;
+
+/// a clock that can be read from the kernel. numeric values match those used by Linux
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum ClockId {
+    /// wall-clock time in nanoseconds since the unix epoch
+    Realtime = 0,
+    /// time since boot in nanoseconds, which never goes backwards
+    Monotonic = 1,
+}
+
+impl TryFrom<u32> for ClockId {
+    type Error = ();
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Realtime),
+            1 => Ok(Self::Monotonic),
+            _ => Err(()),
+        }
+    }
+}
+
+/// a date and time in UTC, as read from a hardware clock
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: u32,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl DateTime {
+    /// converts this date and time to the number of seconds since the unix epoch
+    pub fn to_unix_timestamp(&self) -> i64 {
+        // see http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146_097 + day_of_era - 719_468;
+
+        days * 86400 + i64::from(self.hour) * 3600 + i64::from(self.minute) * 60 + i64::from(self.second)
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", self.year, self.month, self.day, self.hour, self.minute, self.second)
+    }
+}
+
+/// converts an hour from 1-12 with an AM/PM flag to 0-23
+pub const fn hour_from_12_hour(hour: u8, is_pm: bool) -> u8 {
+    let hour = hour % 12;
+    if is_pm {
+        hour + 12
+    } else {
+        hour
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date_time(year: u32, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> DateTime {
+        DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }
+    }
+
+    #[test]
+    fn epoch() {
+        assert_eq!(date_time(1970, 1, 1, 0, 0, 0).to_unix_timestamp(), 0);
+        assert_eq!(date_time(1969, 12, 31, 23, 59, 59).to_unix_timestamp(), -1);
+    }
+
+    #[test]
+    fn leap_day() {
+        assert_eq!(date_time(2000, 2, 29, 0, 0, 0).to_unix_timestamp(), 951_782_400);
+        assert_eq!(date_time(2000, 3, 1, 0, 0, 0).to_unix_timestamp(), 951_782_400 + 86400);
+    }
+
+    #[test]
+    fn twelve_hour() {
+        assert_eq!(hour_from_12_hour(12, false), 0);
+        assert_eq!(hour_from_12_hour(1, false), 1);
+        assert_eq!(hour_from_12_hour(11, false), 11);
+        assert_eq!(hour_from_12_hour(12, true), 12);
+        assert_eq!(hour_from_12_hour(1, true), 13);
+        assert_eq!(hour_from_12_hour(11, true), 23);
+
+        // 12 AM is midnight at the start of the day and 12 PM is noon
+        assert_eq!(date_time(2000, 1, 1, hour_from_12_hour(12, false), 0, 0).to_unix_timestamp(), 946_684_800);
+        assert_eq!(date_time(2000, 1, 1, hour_from_12_hour(12, true), 0, 0).to_unix_timestamp(), 946_684_800 + 12 * 3600);
+    }
+
+    #[test]
+    fn year_2038() {
+        assert_eq!(date_time(2038, 1, 19, 3, 14, 7).to_unix_timestamp(), i64::from(i32::MAX));
+        assert_eq!(date_time(2038, 1, 19, 3, 14, 8).to_unix_timestamp(), i64::from(i32::MAX) + 1);
+    }
+
+    #[test]
+    fn clock_id() {
+        for clock in [ClockId::Realtime, ClockId::Monotonic] {
+            assert_eq!(ClockId::try_from(clock as u32), Ok(clock));
+        }
+        assert_eq!(ClockId::try_from(2), Err(()));
+    }
+}
@@ -0,0 +1,359 @@
+/// signature at the start of the RSDP
+const RSDP_SIGNATURE: &[u8; 8] = b"RSD PTR ";
+
+/// size of the ACPI 1.0 RSDP
+const RSDP_V1_SIZE: usize = 20;
+
+/// size of the ACPI 2.0+ RSDP
+const RSDP_V2_SIZE: usize = 36;
+
+/// physical address of the BIOS data area word holding the segment of the extended BIOS data area
+const EBDA_POINTER: u64 = 0x40e;
+
+/// how much of the extended BIOS data area to search for the RSDP
+const EBDA_SEARCH_SIZE: u64 = 0x400;
+
+/// start of the BIOS read-only memory area that the RSDP can be found in
+const BIOS_AREA_START: u64 = 0xe_0000;
+
+/// end of the BIOS read-only memory area that the RSDP can be found in
+const BIOS_AREA_END: u64 = 0x10_0000;
+
+/// size of the header common to all system description tables
+const SDT_HEADER_SIZE: usize = 36;
+
+/// size of the fixed part of the MADT before its entries
+const MADT_HEADER_SIZE: usize = SDT_HEADER_SIZE + 8;
+
+/// provides access to physical memory so that ACPI tables can be read
+pub trait PhysicalMemory {
+    /// gets a view of the given range of physical memory, or None if it can't be accessed
+    fn read(&self, address: u64, len: usize) -> Option<&[u8]>;
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(bytes.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+/// checks that all the bytes of a table add up to zero, as required by the ACPI spec
+fn is_checksum_valid(bytes: &[u8]) -> bool {
+    bytes.iter().fold(0_u8, |sum, byte| sum.wrapping_add(*byte)) == 0
+}
+
+/// the root system description pointer, which points to all the other tables
+#[derive(Clone, Debug)]
+pub struct Rsdp {
+    pub oem_id: [u8; 6],
+    pub revision: u8,
+    pub rsdt_address: u32,
+    /// only present on ACPI 2.0 and later
+    pub xsdt_address: Option<u64>,
+}
+
+impl Rsdp {
+    /// parses and validates an RSDP at the start of the given bytes
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let v1 = bytes.get(..RSDP_V1_SIZE)?;
+        if &v1[..8] != RSDP_SIGNATURE || !is_checksum_valid(v1) {
+            return None;
+        }
+
+        let revision = v1[15];
+        let xsdt_address = if revision >= 2 {
+            let length = read_u32(bytes, 20)? as usize;
+            if length < RSDP_V2_SIZE || !is_checksum_valid(bytes.get(..length)?) {
+                return None;
+            }
+
+            Some(read_u64(bytes, 24)?).filter(|address| *address != 0)
+        } else {
+            None
+        };
+
+        Some(Self {
+            oem_id: v1[9..15].try_into().ok()?,
+            revision,
+            rsdt_address: read_u32(v1, 16)?,
+            xsdt_address,
+        })
+    }
+}
+
+/// searches the given range of physical memory for the RSDP, which is always aligned to 16 bytes
+fn search_for_rsdp<M: PhysicalMemory>(memory: &M, start: u64, end: u64) -> Option<Rsdp> {
+    let area = memory.read(start, (end - start).try_into().ok()?)?;
+    (0..area.len()).step_by(16).find_map(|offset| Rsdp::parse(&area[offset..]))
+}
+
+/// finds the RSDP by searching the extended BIOS data area and then the BIOS read-only memory area
+pub fn find_rsdp<M: PhysicalMemory>(memory: &M) -> Option<Rsdp> {
+    memory
+        .read(EBDA_POINTER, 2)
+        .and_then(|bytes| read_u16(bytes, 0))
+        .map(|segment| u64::from(segment) << 4)
+        .filter(|ebda| *ebda != 0)
+        .and_then(|ebda| search_for_rsdp(memory, ebda, ebda + EBDA_SEARCH_SIZE))
+        .or_else(|| search_for_rsdp(memory, BIOS_AREA_START, BIOS_AREA_END))
+}
+
+/// the header at the start of every system description table
+#[derive(Clone, Debug)]
+pub struct SdtHeader {
+    pub signature: [u8; 4],
+    pub length: u32,
+    pub revision: u8,
+    pub oem_id: [u8; 6],
+    pub oem_table_id: [u8; 8],
+}
+
+impl SdtHeader {
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.get(..SDT_HEADER_SIZE)?;
+
+        Some(Self {
+            signature: bytes[0..4].try_into().ok()?,
+            length: read_u32(bytes, 4)?,
+            revision: bytes[8],
+            oem_id: bytes[10..16].try_into().ok()?,
+            oem_table_id: bytes[16..24].try_into().ok()?,
+        })
+    }
+}
+
+/// reads a whole system description table at the given physical address, validating its length and checksum
+fn read_table<M: PhysicalMemory>(memory: &M, address: u64) -> Option<(SdtHeader, &[u8])> {
+    let header = SdtHeader::parse(memory.read(address, SDT_HEADER_SIZE)?)?;
+    let length = header.length as usize;
+    if length < SDT_HEADER_SIZE {
+        return None;
+    }
+
+    let table = memory.read(address, length)?;
+    is_checksum_valid(table).then_some((header, table))
+}
+
+/// provides access to the ACPI tables pointed to by an RSDP
+pub struct Acpi<'a, M: PhysicalMemory> {
+    memory: &'a M,
+    rsdp: Rsdp,
+}
+
+impl<'a, M: PhysicalMemory> Acpi<'a, M> {
+    pub fn new(memory: &'a M, rsdp: Rsdp) -> Self {
+        Self { memory, rsdp }
+    }
+
+    /// finds the RSDP in memory and creates an Acpi from it
+    pub fn find(memory: &'a M) -> Option<Self> {
+        find_rsdp(memory).map(|rsdp| Self::new(memory, rsdp))
+    }
+
+    pub fn rsdp(&self) -> &Rsdp {
+        &self.rsdp
+    }
+
+    /// reads the root table at the given address if it's valid and has the given signature, returning its entries
+    fn root_table_entries(&self, address: u64, signature: [u8; 4]) -> Option<&'a [u8]> {
+        read_table(self.memory, address)
+            .filter(|(header, _)| header.signature == signature)
+            .and_then(|(_, table)| table.get(SDT_HEADER_SIZE..))
+    }
+
+    /// gets the physical addresses of all the tables listed in the XSDT, or the RSDT if there's no valid XSDT
+    pub fn table_addresses(&self) -> impl Iterator<Item = u64> + 'a {
+        // some firmware provides a broken XSDT alongside a working RSDT, so fall back to the RSDT rather than finding no tables
+        let (entries, entry_size) = self
+            .rsdp
+            .xsdt_address
+            .and_then(|address| self.root_table_entries(address, *b"XSDT"))
+            .map(|entries| (entries, 8))
+            .or_else(|| self.root_table_entries(self.rsdp.rsdt_address.into(), *b"RSDT").map(|entries| (entries, 4)))
+            .unwrap_or((&[], 4));
+
+        entries.chunks_exact(entry_size).map(move |entry| {
+            if entry_size == 8 {
+                read_u64(entry, 0).unwrap_or_default()
+            } else {
+                read_u32(entry, 0).unwrap_or_default().into()
+            }
+        })
+    }
+
+    /// finds the first valid table with the given signature
+    pub fn find_table(&self, signature: &[u8; 4]) -> Option<(SdtHeader, &'a [u8])> {
+        let memory = self.memory;
+        self.table_addresses()
+            .filter_map(|address| read_table(memory, address))
+            .find(|(header, _)| &header.signature == signature)
+    }
+
+    /// gets the multiple APIC description table, which lists the interrupt controllers and CPUs in the system
+    pub fn madt(&self) -> Option<Madt<'a>> {
+        self.find_table(b"APIC").and_then(|(_, table)| Madt::parse(table))
+    }
+
+    /// gets the fixed ACPI description table, which describes power management hardware
+    pub fn fadt(&self) -> Option<Fadt> {
+        self.find_table(b"FACP").map(|(_, table)| Fadt::parse(table))
+    }
+}
+
+/// a typed view of the multiple APIC description table
+#[derive(Clone, Debug)]
+pub struct Madt<'a> {
+    pub local_apic_address: u32,
+    pub flags: u32,
+    entries: &'a [u8],
+}
+
+impl<'a> Madt<'a> {
+    /// set in the flags if the system also has dual 8259 PICs
+    pub const FLAG_PCAT_COMPAT: u32 = 1;
+
+    pub fn parse(table: &'a [u8]) -> Option<Self> {
+        Some(Self {
+            local_apic_address: read_u32(table, SDT_HEADER_SIZE)?,
+            flags: read_u32(table, SDT_HEADER_SIZE + 4)?,
+            entries: table.get(MADT_HEADER_SIZE..)?,
+        })
+    }
+
+    /// iterates over the entries in the table
+    pub fn entries(&self) -> MadtEntries<'a> {
+        MadtEntries { remaining: self.entries }
+    }
+}
+
+/// an entry in the MADT
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MadtEntry {
+    /// a CPU and its local APIC
+    LocalApic { processor_id: u8, apic_id: u8, flags: u32 },
+    /// an I/O APIC and the first global system interrupt it handles
+    IoApic { id: u8, address: u32, global_interrupt_base: u32 },
+    /// an ISA interrupt that's mapped to a different global system interrupt
+    InterruptSourceOverride { bus: u8, source: u8, global_interrupt: u32, flags: u16 },
+    /// a local APIC interrupt input that's connected to NMI
+    LocalApicNmi { processor_id: u8, flags: u16, lint: u8 },
+    /// a 64-bit address for the local APIC to be used instead of the one in the table header
+    LocalApicAddressOverride { address: u64 },
+    /// an entry type that isn't parsed
+    Other { kind: u8 },
+}
+
+impl MadtEntry {
+    /// set in the flags of a local APIC entry if the CPU is enabled
+    pub const LOCAL_APIC_ENABLED: u32 = 1;
+
+    /// set in the flags of a local APIC entry if the CPU can be brought online even though it's not enabled
+    pub const LOCAL_APIC_ONLINE_CAPABLE: u32 = 2;
+
+    fn parse(kind: u8, entry: &[u8]) -> Option<Self> {
+        Some(match kind {
+            0 => Self::LocalApic {
+                processor_id: *entry.get(2)?,
+                apic_id: *entry.get(3)?,
+                flags: read_u32(entry, 4)?,
+            },
+            1 => Self::IoApic {
+                id: *entry.get(2)?,
+                address: read_u32(entry, 4)?,
+                global_interrupt_base: read_u32(entry, 8)?,
+            },
+            2 => Self::InterruptSourceOverride {
+                bus: *entry.get(2)?,
+                source: *entry.get(3)?,
+                global_interrupt: read_u32(entry, 4)?,
+                flags: read_u16(entry, 8)?,
+            },
+            4 => Self::LocalApicNmi {
+                processor_id: *entry.get(2)?,
+                flags: read_u16(entry, 3)?,
+                lint: *entry.get(5)?,
+            },
+            5 => Self::LocalApicAddressOverride { address: read_u64(entry, 4)? },
+            _ => Self::Other { kind },
+        })
+    }
+}
+
+/// iterator over the entries in the MADT
+pub struct MadtEntries<'a> {
+    remaining: &'a [u8],
+}
+
+impl Iterator for MadtEntries<'_> {
+    type Item = MadtEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let kind = *self.remaining.first()?;
+        let length = *self.remaining.get(1)? as usize;
+
+        // a malformed entry means the rest of the table can't be trusted
+        let Some(entry) = self.remaining.get(..length).filter(|_| length >= 2) else {
+            self.remaining = &[];
+            return None;
+        };
+        self.remaining = &self.remaining[length..];
+
+        MadtEntry::parse(kind, entry).or(Some(MadtEntry::Other { kind }))
+    }
+}
+
+/// a typed view of the fixed ACPI description table. fields that aren't present in older, shorter versions of the table are 0
+#[derive(Clone, Debug, Default)]
+pub struct Fadt {
+    pub firmware_control: u32,
+    pub dsdt: u32,
+    pub preferred_pm_profile: u8,
+    pub sci_interrupt: u16,
+    pub smi_command_port: u32,
+    pub acpi_enable: u8,
+    pub acpi_disable: u8,
+    pub pm1a_event_block: u32,
+    pub pm1b_event_block: u32,
+    pub pm1a_control_block: u32,
+    pub pm1b_control_block: u32,
+    pub pm_timer_block: u32,
+    pub pm_timer_length: u8,
+    /// CMOS RAM index of the RTC's century register, or 0 if there isn't one
+    pub century: u8,
+    pub boot_architecture_flags: u16,
+    pub flags: u32,
+    pub x_dsdt: u64,
+}
+
+impl Fadt {
+    /// set in the boot architecture flags if the system has an 8042 PS/2 controller
+    pub const BOOT_ARCH_8042: u16 = 2;
+
+    pub fn parse(table: &[u8]) -> Self {
+        Self {
+            firmware_control: read_u32(table, 36).unwrap_or_default(),
+            dsdt: read_u32(table, 40).unwrap_or_default(),
+            preferred_pm_profile: table.get(45).copied().unwrap_or_default(),
+            sci_interrupt: read_u16(table, 46).unwrap_or_default(),
+            smi_command_port: read_u32(table, 48).unwrap_or_default(),
+            acpi_enable: table.get(52).copied().unwrap_or_default(),
+            acpi_disable: table.get(53).copied().unwrap_or_default(),
+            pm1a_event_block: read_u32(table, 56).unwrap_or_default(),
+            pm1b_event_block: read_u32(table, 60).unwrap_or_default(),
+            pm1a_control_block: read_u32(table, 64).unwrap_or_default(),
+            pm1b_control_block: read_u32(table, 68).unwrap_or_default(),
+            pm_timer_block: read_u32(table, 76).unwrap_or_default(),
+            pm_timer_length: table.get(91).copied().unwrap_or_default(),
+            century: table.get(108).copied().unwrap_or_default(),
+            boot_architecture_flags: read_u16(table, 109).unwrap_or_default(),
+            flags: read_u32(table, 112).unwrap_or_default(),
+            x_dsdt: read_u64(table, 140).unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use crate::block::{check_transfer, BlockDevice};
+use actias_api::Errno;
+use log::{debug, info};
+use spin::{Mutex, Once};
+use x86::io::{inb, inw, outb, outw};
+
+/// the primary IDE channel
+pub static PRIMARY: Channel = Channel::new(0x1f0, 0x3f6);
+
+/// the secondary IDE channel
+pub static SECONDARY: Channel = Channel::new(0x170, 0x376);
+
+/// size of a sector in bytes
+pub const SECTOR_SIZE: usize = 512;
+
+/// maximum number of sectors that can be transferred with a single command
+const MAX_SECTORS_PER_COMMAND: u64 = 256;
+
+/// highest sector that can be addressed without LBA48
+const MAX_LBA28: u64 = 0x0fff_ffff;
+
+/// how many times to poll the status register before giving up on a drive
+const POLL_ATTEMPTS: usize = 0x10_0000;
+
+const REG_DATA: u16 = 0;
+const REG_SECTOR_COUNT: u16 = 2;
+const REG_LBA_LOW: u16 = 3;
+const REG_LBA_MID: u16 = 4;
+const REG_LBA_HIGH: u16 = 5;
+const REG_DRIVE: u16 = 6;
+const REG_COMMAND: u16 = 7;
+const REG_STATUS: u16 = 7;
+
+const STATUS_ERROR: u8 = 0x01;
+const STATUS_DATA_REQUEST: u8 = 0x08;
+const STATUS_DRIVE_FAULT: u8 = 0x20;
+const STATUS_BUSY: u8 = 0x80;
+
+/// disables interrupts from the channel when written to the device control register
+const CONTROL_NO_INTERRUPTS: u8 = 0x02;
+
+/// resets both drives on the channel while set in the device control register
+const CONTROL_SOFT_RESET: u8 = 0x04;
+
+/// how many times to run the 400ns status delay while holding the channel in reset, which has to be at least 5us
+const RESET_DELAYS: usize = 16;
+
+const DRIVE_LBA: u8 = 0x40;
+const DRIVE_LBA28_BASE: u8 = 0xe0;
+const DRIVE_SELECT_BASE: u8 = 0xa0;
+
+const COMMAND_READ_SECTORS: u8 = 0x20;
+const COMMAND_READ_SECTORS_EXT: u8 = 0x24;
+const COMMAND_WRITE_SECTORS: u8 = 0x30;
+const COMMAND_WRITE_SECTORS_EXT: u8 = 0x34;
+const COMMAND_FLUSH_CACHE: u8 = 0xe7;
+const COMMAND_FLUSH_CACHE_EXT: u8 = 0xea;
+const COMMAND_IDENTIFY: u8 = 0xec;
+
+/// an IDE channel, which can have up to two drives attached to it
+pub struct Channel {
+    io_base: u16,
+    control_base: u16,
+    lock: Mutex<()>,
+}
+
+impl Channel {
+    pub const fn new(io_base: u16, control_base: u16) -> Self {
+        Self {
+            io_base,
+            control_base,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// reads the alternate status register a few times, giving the drive the 400ns it needs to update its status after being selected
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    unsafe fn delay(&self) {
+        for _ in 0..4 {
+            inb(self.control_base);
+        }
+    }
+
+    /// waits for the busy bit to clear, returning the final status
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    unsafe fn wait_not_busy(&self) -> Result<u8, Errno> {
+        for _ in 0..POLL_ATTEMPTS {
+            let status = inb(self.io_base + REG_STATUS);
+            if status & STATUS_BUSY == 0 {
+                return Ok(status);
+            }
+        }
+
+        Err(Errno::TimedOut)
+    }
+
+    /// waits for a command we issued to finish, returning an error if it failed.
+    /// this must only be used after sending a command, since the error bits stay set until the next one is sent
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    unsafe fn wait_complete(&self) -> Result<(), Errno> {
+        if self.wait_not_busy()? & (STATUS_ERROR | STATUS_DRIVE_FAULT) == 0 {
+            Ok(())
+        } else {
+            Err(Errno::IOError)
+        }
+    }
+
+    /// waits for the drive to be ready to transfer data
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    unsafe fn wait_data_request(&self) -> Result<(), Errno> {
+        for _ in 0..POLL_ATTEMPTS {
+            let status = inb(self.io_base + REG_STATUS);
+            if status & STATUS_BUSY != 0 {
+                continue;
+            }
+            if status & (STATUS_ERROR | STATUS_DRIVE_FAULT) != 0 {
+                return Err(Errno::IOError);
+            }
+            if status & STATUS_DATA_REQUEST != 0 {
+                return Ok(());
+            }
+        }
+
+        Err(Errno::TimedOut)
+    }
+
+    /// resets both drives on the channel, aborting whatever they were doing so they don't get stuck waiting for a transfer to finish
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    unsafe fn reset(&self) {
+        outb(self.control_base, CONTROL_NO_INTERRUPTS | CONTROL_SOFT_RESET);
+        for _ in 0..RESET_DELAYS {
+            self.delay();
+        }
+        outb(self.control_base, CONTROL_NO_INTERRUPTS);
+        self.delay();
+
+        // there's nothing else to do if the drives don't come back, the next command will time out too
+        let _ = self.wait_not_busy();
+    }
+
+    /// reads a sector's worth of data from the data register
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    unsafe fn read_sector_data(&self, buf: &mut [u8]) {
+        for chunk in buf.chunks_exact_mut(2) {
+            chunk.copy_from_slice(&inw(self.io_base + REG_DATA).to_le_bytes());
+        }
+    }
+
+    /// writes a sector's worth of data to the data register
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    unsafe fn write_sector_data(&self, buf: &[u8]) {
+        for chunk in buf.chunks_exact(2) {
+            outw(self.io_base + REG_DATA, u16::from_le_bytes([chunk[0], chunk[1]]));
+        }
+    }
+}
+
+/// which of the two drives on a channel to use
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DriveSelect {
+    Master,
+    Slave,
+}
+
+impl DriveSelect {
+    fn bits(self) -> u8 {
+        match self {
+            Self::Master => 0,
+            Self::Slave => 0x10,
+        }
+    }
+}
+
+/// an ATA hard drive accessed with PIO
+pub struct Drive {
+    channel: &'static Channel,
+    select: DriveSelect,
+    num_sectors: u64,
+    supports_lba48: bool,
+    model: [u8; 40],
+}
+
+impl Drive {
+    /// checks whether there's an ATA drive at the given location, returning it if so
+    pub fn identify(channel: &'static Channel, select: DriveSelect) -> Option<Self> {
+        let _guard = channel.lock.lock();
+        let mut identify = [0; SECTOR_SIZE];
+
+        unsafe {
+            outb(channel.control_base, CONTROL_NO_INTERRUPTS);
+
+            // a floating bus reads as all ones, meaning there's nothing attached to this channel
+            if inb(channel.io_base + REG_STATUS) == 0xff {
+                return None;
+            }
+
+            outb(channel.io_base + REG_DRIVE, DRIVE_SELECT_BASE | select.bits());
+            channel.delay();
+
+            outb(channel.io_base + REG_SECTOR_COUNT, 0);
+            outb(channel.io_base + REG_LBA_LOW, 0);
+            outb(channel.io_base + REG_LBA_MID, 0);
+            outb(channel.io_base + REG_LBA_HIGH, 0);
+            outb(channel.io_base + REG_COMMAND, COMMAND_IDENTIFY);
+
+            if inb(channel.io_base + REG_STATUS) == 0 {
+                return None;
+            }
+
+            channel.wait_not_busy().ok()?;
+
+            // ATAPI and SATA devices set these to a signature rather than aborting the command cleanly
+            if inb(channel.io_base + REG_LBA_MID) != 0 || inb(channel.io_base + REG_LBA_HIGH) != 0 {
+                return None;
+            }
+
+            channel.wait_data_request().ok()?;
+            channel.read_sector_data(&mut identify);
+        }
+
+        let word = |index: usize| u64::from(u16::from_le_bytes([identify[index * 2], identify[index * 2 + 1]]));
+
+        let supports_lba48 = word(83) & (1 << 10) != 0;
+        let num_sectors = if supports_lba48 {
+            word(100) | (word(101) << 16) | (word(102) << 32) | (word(103) << 48)
+        } else {
+            word(60) | (word(61) << 16)
+        };
+
+        // the model string is stored as big-endian words
+        let mut model = [0; 40];
+        for (dest, src) in model.chunks_exact_mut(2).zip(identify[54..94].chunks_exact(2)) {
+            dest[0] = src[1];
+            dest[1] = src[0];
+        }
+
+        Some(Self {
+            channel,
+            select,
+            num_sectors,
+            supports_lba48,
+            model,
+        })
+    }
+
+    /// gets the model name reported by the drive
+    pub fn model(&self) -> &str {
+        core::str::from_utf8(&self.model).unwrap_or("unknown").trim()
+    }
+
+    /// selects this drive and waits for it to be ready for a new command. errors from the previous command are ignored
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    unsafe fn select_drive(&self, bits: u8) -> Result<(), Errno> {
+        outb(self.channel.io_base + REG_DRIVE, bits | self.select.bits());
+        self.channel.delay();
+        self.channel.wait_not_busy().map(|_| ())
+    }
+
+    /// sets up the drive select and address registers and sends a read or write command
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    unsafe fn send_command(&self, lba: u64, count: u64, command: u8, command_ext: u8) -> Result<(), Errno> {
+        let io_base = self.channel.io_base;
+        let lba_bytes = lba.to_le_bytes();
+
+        // with LBA28 only the low byte is used, and a count of 256 wraps around to 0 which the drive takes to mean 256
+        let [count_low, count_high, ..] = count.to_le_bytes();
+
+        if lba + count - 1 > MAX_LBA28 {
+            if !self.supports_lba48 {
+                return Err(Errno::InvalidArgument);
+            }
+
+            self.select_drive(DRIVE_LBA)?;
+
+            outb(io_base + REG_SECTOR_COUNT, count_high);
+            outb(io_base + REG_LBA_LOW, lba_bytes[3]);
+            outb(io_base + REG_LBA_MID, lba_bytes[4]);
+            outb(io_base + REG_LBA_HIGH, lba_bytes[5]);
+            outb(io_base + REG_SECTOR_COUNT, count_low);
+            outb(io_base + REG_LBA_LOW, lba_bytes[0]);
+            outb(io_base + REG_LBA_MID, lba_bytes[1]);
+            outb(io_base + REG_LBA_HIGH, lba_bytes[2]);
+            outb(io_base + REG_COMMAND, command_ext);
+        } else {
+            self.select_drive(DRIVE_LBA28_BASE | (lba_bytes[3] & 0xf))?;
+
+            outb(io_base + REG_SECTOR_COUNT, count_low);
+            outb(io_base + REG_LBA_LOW, lba_bytes[0]);
+            outb(io_base + REG_LBA_MID, lba_bytes[1]);
+            outb(io_base + REG_LBA_HIGH, lba_bytes[2]);
+            outb(io_base + REG_COMMAND, command);
+        }
+
+        Ok(())
+    }
+
+    /// flushes the drive's write cache
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    unsafe fn flush(&self) -> Result<(), Errno> {
+        let command = if self.supports_lba48 { COMMAND_FLUSH_CACHE_EXT } else { COMMAND_FLUSH_CACHE };
+
+        // the drive ignores register writes while it's still busy committing the last sector
+        self.select_drive(DRIVE_SELECT_BASE)?;
+        outb(self.channel.io_base + REG_COMMAND, command);
+
+        self.channel.wait_complete()
+    }
+}
+
+impl BlockDevice for Drive {
+    fn block_size(&self) -> usize {
+        SECTOR_SIZE
+    }
+
+    fn num_blocks(&self) -> u64 {
+        self.num_sectors
+    }
+
+    fn read_blocks(&self, start: u64, buf: &mut [u8]) -> Result<(), Errno> {
+        check_transfer(self, start, buf.len())?;
+
+        let _guard = self.channel.lock.lock();
+        let mut lba = start;
+
+        for chunk in buf.chunks_mut(SECTOR_SIZE * MAX_SECTORS_PER_COMMAND as usize) {
+            let count = (chunk.len() / SECTOR_SIZE) as u64;
+
+            unsafe {
+                self.send_command(lba, count, COMMAND_READ_SECTORS, COMMAND_READ_SECTORS_EXT)?;
+
+                for sector in chunk.chunks_exact_mut(SECTOR_SIZE) {
+                    if let Err(error) = self.channel.wait_data_request() {
+                        // don't leave the drive waiting for the rest of the transfer
+                        self.channel.reset();
+                        return Err(error);
+                    }
+                    self.channel.read_sector_data(sector);
+                }
+
+                self.channel.wait_complete()?;
+            }
+
+            lba += count;
+        }
+
+        Ok(())
+    }
+
+    fn write_blocks(&self, start: u64, buf: &[u8]) -> Result<(), Errno> {
+        check_transfer(self, start, buf.len())?;
+
+        let _guard = self.channel.lock.lock();
+        let mut lba = start;
+
+        for chunk in buf.chunks(SECTOR_SIZE * MAX_SECTORS_PER_COMMAND as usize) {
+            let count = (chunk.len() / SECTOR_SIZE) as u64;
+
+            unsafe {
+                self.send_command(lba, count, COMMAND_WRITE_SECTORS, COMMAND_WRITE_SECTORS_EXT)?;
+
+                for sector in chunk.chunks_exact(SECTOR_SIZE) {
+                    if let Err(error) = self.channel.wait_data_request() {
+                        // don't leave the drive waiting for the rest of the transfer
+                        self.channel.reset();
+                        return Err(error);
+                    }
+                    self.channel.write_sector_data(sector);
+                }
+
+                self.channel.wait_complete()?;
+            }
+
+            lba += count;
+        }
+
+        unsafe { self.flush() }
+    }
+}
+
+/// all the drives found at boot, indexed as primary master, primary slave, secondary master, secondary slave
+static DRIVES: Once<[Option<Drive>; 4]> = Once::new();
+
+/// probes for drives on the standard IDE channels and logs what was found
+pub fn init() -> &'static [Option<Drive>; 4] {
+    DRIVES.call_once(|| {
+        let drives = [
+            Drive::identify(&PRIMARY, DriveSelect::Master),
+            Drive::identify(&PRIMARY, DriveSelect::Slave),
+            Drive::identify(&SECONDARY, DriveSelect::Master),
+            Drive::identify(&SECONDARY, DriveSelect::Slave),
+        ];
+
+        for (index, drive) in drives.iter().enumerate() {
+            match drive {
+                Some(drive) => info!("ata{index}: {}, {} sectors ({} MiB)", drive.model(), drive.num_blocks(), drive.size() / (1024 * 1024)),
+                None => debug!("ata{index}: no drive"),
+            }
+        }
+
+        drives
+    })
+}
+
+/// gets the drives found at boot, if they've been probed
+pub fn drives() -> Option<&'static [Option<Drive>; 4]> {
+    DRIVES.get()
+}
@@ -0,0 +1,201 @@
+use core::{
+    arch::x86::{__cpuid, __cpuid_count, has_cpuid, CpuidResult},
+    fmt,
+};
+use spin::Once;
+
+/// which register of a CPUID result a feature flag is stored in
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Register {
+    Ebx,
+    Ecx,
+    Edx,
+}
+
+/// defines the Feature enum along with where each flag is found and its name from a single table, so they can't drift apart
+macro_rules! features {
+    ($($name:ident = ($leaf:expr, $register:ident, $bit:literal) => $display:literal,)*) => {
+        /// CPU features that the kernel knows how to detect
+        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+        #[repr(u8)]
+        pub enum Feature {
+            $($name,)*
+        }
+
+        impl Feature {
+            /// every feature, in the order they're reported in
+            pub const ALL: &'static [Self] = &[$(Self::$name,)*];
+
+            /// the CPUID leaf, register, and bit this feature's flag is found at
+            const fn location(self) -> (u32, Register, u32) {
+                match self {
+                    $(Self::$name => ($leaf, Register::$register, $bit),)*
+                }
+            }
+
+            /// the name of this feature as it's shown in feature lists
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(Self::$name => $display,)*
+                }
+            }
+        }
+    };
+}
+
+features! {
+    FPU = (1, Edx, 0) => "fpu",
+    PSE = (1, Edx, 3) => "pse",
+    TSC = (1, Edx, 4) => "tsc",
+    MSR = (1, Edx, 5) => "msr",
+    PAE = (1, Edx, 6) => "pae",
+    CX8 = (1, Edx, 8) => "cx8",
+    APIC = (1, Edx, 9) => "apic",
+    SEP = (1, Edx, 11) => "sep",
+    PGE = (1, Edx, 13) => "pge",
+    CMOV = (1, Edx, 15) => "cmov",
+    PAT = (1, Edx, 16) => "pat",
+    FXSR = (1, Edx, 24) => "fxsr",
+    SSE = (1, Edx, 25) => "sse",
+    SSE2 = (1, Edx, 26) => "sse2",
+    SSE3 = (1, Ecx, 0) => "sse3",
+    X2APIC = (1, Ecx, 21) => "x2apic",
+    TSCDeadline = (1, Ecx, 24) => "tsc_deadline",
+    XSAVE = (1, Ecx, 26) => "xsave",
+    RDRAND = (1, Ecx, 30) => "rdrand",
+    Hypervisor = (1, Ecx, 31) => "hypervisor",
+    SMEP = (7, Ebx, 7) => "smep",
+    RDSEED = (7, Ebx, 18) => "rdseed",
+    SMAP = (7, Ebx, 20) => "smap",
+    NX = (0x8000_0001, Edx, 20) => "nx",
+    InvariantTSC = (0x8000_0007, Edx, 8) => "invariant_tsc",
+}
+
+/// identification and feature information for a CPU, as reported by CPUID
+#[derive(Clone, Debug)]
+pub struct CpuInfo {
+    vendor: [u8; 12],
+    pub family: u32,
+    pub model: u32,
+    pub stepping: u32,
+    features: u32,
+}
+
+impl CpuInfo {
+    /// gets the vendor ID string of this CPU (i.e. "GenuineIntel")
+    pub fn vendor(&self) -> &str {
+        core::str::from_utf8(&self.vendor).unwrap_or("unknown")
+    }
+
+    /// checks whether this CPU supports the given feature
+    pub fn has_feature(&self, feature: Feature) -> bool {
+        self.features & (1 << feature as u8) != 0
+    }
+
+    /// returns an object that formats the list of supported features separated by spaces
+    pub fn feature_list(&self) -> FeatureList<'_> {
+        FeatureList(self)
+    }
+}
+
+/// helper to format the features supported by a CPU
+pub struct FeatureList<'a>(&'a CpuInfo);
+
+impl fmt::Display for FeatureList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+
+        for feature in Feature::ALL.iter().copied().filter(|feature| self.0.has_feature(*feature)) {
+            if !first {
+                f.write_str(" ")?;
+            }
+            f.write_str(feature.name())?;
+            first = false;
+        }
+
+        Ok(())
+    }
+}
+
+/// information about the bootstrap processor, filled in at boot
+static BOOT_CPU: Once<CpuInfo> = Once::new();
+
+/// runs CPUID on the current CPU and collects its identification and feature flags
+pub fn detect() -> Option<CpuInfo> {
+    if !has_cpuid() {
+        return None;
+    }
+
+    let CpuidResult { eax: max_leaf, ebx, ecx, edx } = unsafe { __cpuid(0) };
+
+    // CPUs without extended leaves return leftover data from the highest basic leaf instead, which can't be trusted
+    let max_extended_leaf = unsafe { __cpuid(0x8000_0000) }.eax;
+    let max_extended_leaf = if max_extended_leaf >= 0x8000_0000 { max_extended_leaf } else { 0 };
+
+    let mut vendor = [0; 12];
+    vendor[0..4].copy_from_slice(&ebx.to_le_bytes());
+    vendor[4..8].copy_from_slice(&edx.to_le_bytes());
+    vendor[8..12].copy_from_slice(&ecx.to_le_bytes());
+
+    let (family, model, stepping) = if max_leaf >= 1 {
+        let signature = unsafe { __cpuid(1) }.eax;
+
+        let mut family = (signature >> 8) & 0xf;
+        let mut model = (signature >> 4) & 0xf;
+        if family == 0xf {
+            family += (signature >> 20) & 0xff;
+        }
+        if family == 0x6 || family >= 0xf {
+            model |= ((signature >> 16) & 0xf) << 4;
+        }
+
+        (family, model, signature & 0xf)
+    } else {
+        (0, 0, 0)
+    };
+
+    let mut features = 0;
+    for &feature in Feature::ALL {
+        let (leaf, register, bit) = feature.location();
+
+        let is_supported = if leaf >= 0x8000_0000 { leaf <= max_extended_leaf } else { leaf <= max_leaf };
+        if !is_supported {
+            continue;
+        }
+
+        let result = unsafe { __cpuid_count(leaf, 0) };
+        let value = match register {
+            Register::Ebx => result.ebx,
+            Register::Ecx => result.ecx,
+            Register::Edx => result.edx,
+        };
+
+        if value & (1 << bit) != 0 {
+            features |= 1 << feature as u8;
+        }
+    }
+
+    Some(CpuInfo {
+        vendor,
+        family,
+        model,
+        stepping,
+        features,
+    })
+}
+
+/// detects the features of the bootstrap processor and stores them for later use
+pub fn init() -> Option<&'static CpuInfo> {
+    let info = detect()?;
+    Some(BOOT_CPU.call_once(|| info))
+}
+
+/// gets the stored information about the bootstrap processor, if it's been detected
+pub fn boot_cpu() -> Option<&'static CpuInfo> {
+    BOOT_CPU.get()
+}
+
+/// checks whether the bootstrap processor supports the given feature
+pub fn has_feature(feature: Feature) -> bool {
+    boot_cpu().is_some_and(|info| info.has_feature(feature))
+}
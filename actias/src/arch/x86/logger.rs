@@ -1,7 +1,18 @@
+use super::uart::COM1;
 use crate::logger::Logger;
-use core::{fmt, fmt::Write};
-use log::{LevelFilter, SetLoggerError};
-use x86::io::{inb, outb};
+use core::{
+    fmt,
+    fmt::Write,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use log::{warn, LevelFilter, SetLoggerError};
+use x86::io::outb;
+
+/// baud rate to set the serial port to
+const BAUD_RATE: u32 = 115_200;
+
+/// whether COM1 passed its loopback test. if it didn't, whatever's there might not be a UART, so it's left alone
+static HAS_SERIAL: AtomicBool = AtomicBool::new(false);
 
 /// Write a string to the output channel
 ///
@@ -20,12 +31,10 @@ pub unsafe fn serial_puts(s: &str) {
 ///
 /// This method is unsafe because it does port accesses without synchronisation
 pub unsafe fn serial_putb(b: u8) {
-    // Wait for the serial port's fifo to not be empty
-    while (inb(0x3F8 + 5) & 0x20) == 0 {
-        // Do nothing
-    }
     // Send the byte out the serial port
-    outb(0x3F8, b);
+    if HAS_SERIAL.load(Ordering::Relaxed) {
+        COM1.write_byte(b);
+    }
 
     // Also send to the bochs 0xe9 hack
     outb(0xe9, b);
@@ -48,5 +57,14 @@ static LOGGER: Logger<SerialWriter> = Logger::new(LevelFilter::Info, SerialWrite
 
 /// initialize the logger, setting the max level in the process
 pub fn init() -> Result<(), SetLoggerError> {
-    log::set_logger(&LOGGER).map(|_| log::set_max_level(LOGGER.max_level))
+    let has_serial = unsafe { COM1.init(BAUD_RATE) };
+    HAS_SERIAL.store(has_serial, Ordering::Relaxed);
+
+    log::set_logger(&LOGGER).map(|_| log::set_max_level(LOGGER.max_level))?;
+
+    if !has_serial {
+        warn!("COM1 failed its loopback test, log output may only be visible through port 0xe9");
+    }
+
+    Ok(())
 }
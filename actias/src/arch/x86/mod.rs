@@ -1,6 +1,16 @@
+pub mod acpi;
+pub mod ata;
+pub mod cpufeatures;
 mod logger;
+pub mod pit;
+pub mod random;
+pub mod rtc;
+pub mod tsc;
+pub mod uart;
 
-use log::info;
+use crate::clocksource::{self, ClockSource};
+use core::ptr::addr_of;
+use log::{info, warn};
 
 /// the address the kernel is linked at
 pub const LINKED_BASE: usize = 0xe000_0000;
@@ -20,11 +30,66 @@ extern "C" {
     static stack_end: u8;
 }
 
+/// logs a summary of where memory went during boot as key=value pairs, so it can be easily parsed by test tooling
+fn log_boot_summary() {
+    let image_base = unsafe { addr_of!(kernel_start) as usize };
+    let image_size = unsafe { addr_of!(kernel_end) as usize } - image_base;
+    let stack_size = unsafe { addr_of!(stack_end) as usize - addr_of!(stack_base) as usize };
+
+    info!("boot summary: kernel_base={image_base:#x} kernel_image={image_size} stack={stack_size}");
+}
+
+/// sets up every clock source that's available and picks the best one to keep time with
+fn init_clocksource() {
+    unsafe {
+        pit::PIT.init();
+    }
+
+    let tsc = tsc::init();
+    if let Some(tsc) = tsc {
+        info!("TSC runs at {} kHz", tsc.frequency() / 1000);
+    }
+
+    let sources: [Option<&'static dyn ClockSource>; 2] = [Some(&pit::PIT), tsc.map(|tsc| tsc as _)];
+    if let Some(source) = clocksource::select(sources.into_iter().flatten()) {
+        info!("using {} as the clock source", source.name());
+    } else {
+        warn!("no clock source that can keep time, timestamps will be 0");
+    }
+}
+
 /// ran by boot.S when paging has been successfully initialized
 #[no_mangle]
 extern "C" fn kmain() {
     logger::init().unwrap();
     info!("HellOwOrld! :3");
+    info!("userspace ABI version {}", actias_api::ABI_VERSION);
+
+    if let Some(cpu) = cpufeatures::init() {
+        info!("CPU: {} family {:#x} model {:#x} stepping {}", cpu.vendor(), cpu.family, cpu.model, cpu.stepping);
+        info!("CPU features: {}", cpu.feature_list());
+    } else {
+        warn!("CPUID isn't supported, assuming no optional CPU features");
+    }
+
+    init_clocksource();
+
+    if let Some(boot_id) = crate::boot_id::init(random::random) {
+        info!("boot ID: {boot_id}");
+    } else {
+        warn!("no source of random numbers, not generating a boot ID");
+    }
+
+    if let Some(now) = rtc::read() {
+        info!("current time is {now} (unix time {})", now.to_unix_timestamp());
+        crate::time::init(now);
+    } else {
+        warn!("couldn't read the time from the RTC, it may not be present");
+    }
+
+    ata::init();
+
+    log_boot_summary();
 
     loop {}
 }
@@ -0,0 +1,142 @@
+use crate::clocksource::ClockSource;
+use spin::Mutex;
+use x86::io::{inb, outb};
+
+/// frequency of the PIT's input clock in Hz
+pub const PIT_FREQUENCY: u64 = 1_193_182;
+
+const CHANNEL_0_DATA: u16 = 0x40;
+const CHANNEL_2_DATA: u16 = 0x42;
+const COMMAND: u16 = 0x43;
+
+/// the NMI status and control port, which holds channel 2's gate and output bits
+const PORT_61: u16 = 0x61;
+
+/// channel 0, low then high byte access, mode 2 (rate generator), binary
+const COMMAND_CHANNEL_0_RATE_GENERATOR: u8 = 0x34;
+
+/// channel 0 counter latch
+const COMMAND_CHANNEL_0_LATCH: u8 = 0x00;
+
+/// channel 2, low then high byte access, mode 0 (interrupt on terminal count), binary
+const COMMAND_CHANNEL_2_ONE_SHOT: u8 = 0xb0;
+
+/// set in port 0x61 to let channel 2 count
+const PORT_61_CHANNEL_2_GATE: u8 = 0x01;
+
+/// set in port 0x61 to connect channel 2 to the PC speaker
+const PORT_61_SPEAKER_DATA: u8 = 0x02;
+
+/// set in port 0x61 when channel 2's output is high
+const PORT_61_CHANNEL_2_OUTPUT: u8 = 0x20;
+
+/// how many times to poll channel 2 before assuming it isn't counting
+const POLL_ATTEMPTS: usize = 0x10_0000;
+
+/// rating of the PIT as a clock source. without a timer interrupt to extend the counter it can't keep time, so it's never picked
+const RATING: u32 = 0;
+
+/// channel 0 of the PIT, used as a clock source.
+/// since there's no timer interrupt yet, the 16 bit counter is only extended to 64 bits when it's read,
+/// so time will be lost if it isn't read at least once per wraparound (about 55ms). until an IRQ0 handler does that reading,
+/// it's rated 0 and only used to calibrate other sources
+pub static PIT: Pit = Pit::new();
+
+pub struct Pit {
+    /// the counter value from the last read, and the total number of ticks counted up to then
+    state: Mutex<(u16, u64)>,
+}
+
+impl Pit {
+    const fn new() -> Self {
+        Self { state: Mutex::new((0, 0)) }
+    }
+
+    /// sets channel 0 to count down continuously through its whole range
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    pub unsafe fn init(&self) {
+        let mut state = self.state.lock();
+
+        outb(COMMAND, COMMAND_CHANNEL_0_RATE_GENERATOR);
+        // a reload value of 0 means 65536
+        outb(CHANNEL_0_DATA, 0);
+        outb(CHANNEL_0_DATA, 0);
+
+        state.0 = read_channel_0();
+    }
+}
+
+impl ClockSource for Pit {
+    fn name(&self) -> &'static str {
+        "pit"
+    }
+
+    fn rating(&self) -> u32 {
+        RATING
+    }
+
+    fn frequency(&self) -> u64 {
+        PIT_FREQUENCY
+    }
+
+    fn read(&self) -> u64 {
+        update(&mut self.state.lock())
+    }
+
+    fn try_read(&self) -> Option<u64> {
+        self.state.try_lock().map(|mut state| update(&mut state))
+    }
+}
+
+/// reads channel 0 and adds the ticks since the last read to the total, returning the new total
+fn update(state: &mut (u16, u64)) -> u64 {
+    let (last, total) = *state;
+    let count = unsafe { read_channel_0() };
+
+    // the counter counts down, so the difference is the other way around
+    let total = total + u64::from(last.wrapping_sub(count));
+    *state = (count, total);
+
+    total
+}
+
+/// latches and reads the current count of channel 0
+///
+/// # Safety
+///
+/// This method is unsafe because it does port accesses without synchronisation
+unsafe fn read_channel_0() -> u16 {
+    outb(COMMAND, COMMAND_CHANNEL_0_LATCH);
+    let low = inb(CHANNEL_0_DATA);
+    let high = inb(CHANNEL_0_DATA);
+    u16::from_le_bytes([low, high])
+}
+
+/// measures how much the given counter advances while channel 2 counts down the given number of PIT ticks.
+/// returns None if channel 2 never finishes counting
+///
+/// # Safety
+///
+/// This method is unsafe because it does port accesses without synchronisation
+pub unsafe fn measure<F: FnMut() -> u64>(ticks: u16, mut read: F) -> Option<u64> {
+    let control = inb(PORT_61);
+
+    // enable the gate so the channel counts, but keep the speaker quiet
+    outb(PORT_61, (control & !PORT_61_SPEAKER_DATA) | PORT_61_CHANNEL_2_GATE);
+
+    let [low, high] = ticks.to_le_bytes();
+    outb(COMMAND, COMMAND_CHANNEL_2_ONE_SHOT);
+    outb(CHANNEL_2_DATA, low);
+    outb(CHANNEL_2_DATA, high);
+
+    let start = read();
+    let finished = (0..POLL_ATTEMPTS).find(|_| inb(PORT_61) & PORT_61_CHANNEL_2_OUTPUT != 0);
+    let end = read();
+
+    outb(PORT_61, control);
+
+    finished.map(|_| end.wrapping_sub(start))
+}
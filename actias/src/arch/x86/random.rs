@@ -0,0 +1,41 @@
+use super::cpufeatures::{has_feature, Feature};
+use core::arch::x86::{_rdrand32_step, _rdtsc};
+
+/// how many times to retry RDRAND before giving up, as recommended by Intel
+const RDRAND_RETRIES: usize = 10;
+
+/// gets a random number from the CPU's hardware RNG, if it has one
+pub fn hardware_random() -> Option<u32> {
+    if !has_feature(Feature::RDRAND) {
+        return None;
+    }
+
+    for _ in 0..RDRAND_RETRIES {
+        let mut value = 0;
+        if unsafe { _rdrand32_step(&mut value) } == 1 {
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+/// gets a random number, falling back to hashing the timestamp counter if there's no hardware RNG.
+/// the fallback is only good enough for unique identifiers, not for anything security sensitive
+pub fn random() -> Option<u32> {
+    if let Some(value) = hardware_random() {
+        return Some(value);
+    }
+
+    if !has_feature(Feature::TSC) {
+        return None;
+    }
+
+    // splitmix64 finalizer, to spread the low bits of the timestamp across the whole value
+    let mut value = unsafe { _rdtsc() };
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^= value >> 31;
+
+    Some(value as u32)
+}
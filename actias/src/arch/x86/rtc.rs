@@ -0,0 +1,106 @@
+use crate::time::DateTime;
+use actias_api::time::hour_from_12_hour;
+use x86::io::{inb, outb};
+
+/// port used to select a CMOS register
+const CMOS_ADDRESS: u16 = 0x70;
+
+/// port used to read the selected CMOS register
+const CMOS_DATA: u16 = 0x71;
+
+const REG_SECONDS: u8 = 0x00;
+const REG_MINUTES: u8 = 0x02;
+const REG_HOURS: u8 = 0x04;
+const REG_DAY: u8 = 0x07;
+const REG_MONTH: u8 = 0x08;
+const REG_YEAR: u8 = 0x09;
+const REG_STATUS_A: u8 = 0x0a;
+const REG_STATUS_B: u8 = 0x0b;
+
+/// set in status register A while the RTC is updating its registers
+const STATUS_A_UPDATE_IN_PROGRESS: u8 = 0x80;
+
+/// set in status register B if the hours register uses 24 hour time
+const STATUS_B_24_HOUR: u8 = 0x02;
+
+/// set in status register B if registers are in binary rather than BCD
+const STATUS_B_BINARY: u8 = 0x04;
+
+/// set in the hours register for PM times in 12 hour mode
+const HOURS_PM: u8 = 0x80;
+
+/// how many times to poll the RTC before assuming there isn't one
+const POLL_ATTEMPTS: usize = 0x10_0000;
+
+/// reads a register from the CMOS
+///
+/// # Safety
+///
+/// This method is unsafe because it does port accesses without synchronisation
+unsafe fn read_register(register: u8) -> u8 {
+    outb(CMOS_ADDRESS, register);
+    inb(CMOS_DATA)
+}
+
+/// reads the raw values of all the time registers, waiting for any update in progress to finish first.
+/// returns None if the update never finishes, which happens when there's no RTC and the registers read as all ones
+///
+/// # Safety
+///
+/// This method is unsafe because it does port accesses without synchronisation
+unsafe fn read_raw() -> Option<[u8; 6]> {
+    (0..POLL_ATTEMPTS).find(|_| read_register(REG_STATUS_A) & STATUS_A_UPDATE_IN_PROGRESS == 0)?;
+
+    Some([REG_SECONDS, REG_MINUTES, REG_HOURS, REG_DAY, REG_MONTH, REG_YEAR].map(|register| read_register(register)))
+}
+
+/// converts a BCD value to binary
+fn from_bcd(value: u8) -> u8 {
+    (value >> 4) * 10 + (value & 0xf)
+}
+
+/// reads the current date and time from the CMOS real time clock, assuming it's set to UTC.
+/// returns None if there's no RTC or it never gives a consistent reading
+pub fn read() -> Option<DateTime> {
+    let (raw, status_b) = unsafe {
+        // keep reading until two reads in a row agree, so we don't get values from partway through an update
+        let mut raw = read_raw()?;
+        let mut is_consistent = false;
+        for _ in 0..POLL_ATTEMPTS {
+            let next = read_raw()?;
+            if next == raw {
+                is_consistent = true;
+                break;
+            }
+            raw = next;
+        }
+
+        if !is_consistent {
+            return None;
+        }
+
+        (raw, read_register(REG_STATUS_B))
+    };
+
+    let [second, minute, hour, day, month, year] = raw;
+    let is_pm = hour & HOURS_PM != 0;
+    let [second, minute, mut hour, day, month, year] = if status_b & STATUS_B_BINARY == 0 {
+        [second, minute, hour & !HOURS_PM, day, month, year].map(from_bcd)
+    } else {
+        [second, minute, hour & !HOURS_PM, day, month, year]
+    };
+
+    if status_b & STATUS_B_24_HOUR == 0 {
+        hour = hour_from_12_hour(hour, is_pm);
+    }
+
+    // the century register isn't reliably present, so assume we're in the 2000s
+    Some(DateTime {
+        year: 2000 + u32::from(year),
+        month,
+        day,
+        hour,
+        minute,
+        second,
+    })
+}
@@ -0,0 +1,54 @@
+use super::{
+    cpufeatures::{has_feature, Feature},
+    pit::{self, PIT_FREQUENCY},
+};
+use crate::clocksource::ClockSource;
+use core::arch::x86::_rdtsc;
+use spin::Once;
+
+/// how many PIT ticks to calibrate the TSC over, which is about 50ms
+const CALIBRATION_TICKS: u16 = 59_659;
+
+/// rating of the TSC as a clock source when it runs at a constant rate. it's the fastest and most precise source there is
+const RATING: u32 = 300;
+
+/// the timestamp counter, used as a clock source when it's invariant
+pub struct Tsc {
+    frequency: u64,
+}
+
+static TSC: Once<Option<Tsc>> = Once::new();
+
+/// works out the frequency of the TSC by timing it against the PIT.
+/// returns None if the CPU doesn't have an invariant TSC, since one that changes speed with the CPU clock can't keep time
+pub fn init() -> Option<&'static Tsc> {
+    TSC.call_once(|| {
+        if !has_feature(Feature::TSC) || !has_feature(Feature::InvariantTSC) {
+            return None;
+        }
+
+        let elapsed = unsafe { pit::measure(CALIBRATION_TICKS, || _rdtsc()) }?;
+        let frequency = elapsed * PIT_FREQUENCY / u64::from(CALIBRATION_TICKS);
+
+        (frequency > 0).then_some(Tsc { frequency })
+    })
+    .as_ref()
+}
+
+impl ClockSource for Tsc {
+    fn name(&self) -> &'static str {
+        "tsc"
+    }
+
+    fn rating(&self) -> u32 {
+        RATING
+    }
+
+    fn frequency(&self) -> u64 {
+        self.frequency
+    }
+
+    fn read(&self) -> u64 {
+        unsafe { _rdtsc() }
+    }
+}
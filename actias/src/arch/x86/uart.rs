@@ -0,0 +1,110 @@
+use x86::io::{inb, outb};
+
+/// the first serial port
+pub static COM1: Uart = Uart::new(0x3f8);
+
+/// frequency of the clock that the baud rate is divided down from
+const BASE_BAUD_RATE: u32 = 115_200;
+
+const REG_DATA: u16 = 0;
+const REG_INTERRUPT_ENABLE: u16 = 1;
+const REG_FIFO_CONTROL: u16 = 2;
+const REG_LINE_CONTROL: u16 = 3;
+const REG_MODEM_CONTROL: u16 = 4;
+const REG_LINE_STATUS: u16 = 5;
+
+/// divisor latch access bit in the line control register, which remaps the data and interrupt enable registers to the baud rate divisor
+const LINE_CONTROL_DLAB: u8 = 0x80;
+
+/// 8 data bits, no parity, one stop bit
+const LINE_CONTROL_8N1: u8 = 0x03;
+
+/// enables and clears the FIFOs, with a 14 byte interrupt threshold
+const FIFO_CONTROL_ENABLE: u8 = 0xc7;
+
+/// DTR, RTS, and OUT2 (which gates the interrupt line)
+const MODEM_CONTROL_NORMAL: u8 = 0x0b;
+
+/// RTS, OUT1, OUT2, and loopback mode
+const MODEM_CONTROL_LOOPBACK: u8 = 0x1e;
+
+/// set in the line status register when there's data to be read
+const LINE_STATUS_DATA_READY: u8 = 0x01;
+
+/// set in the line status register when the transmit holding register is empty
+const LINE_STATUS_TRANSMIT_EMPTY: u8 = 0x20;
+
+/// byte sent in loopback mode to check that the UART is actually there
+const LOOPBACK_TEST_BYTE: u8 = 0xae;
+
+/// how many times to poll the line status register before giving up on sending a byte
+const POLL_ATTEMPTS: usize = 0x10_0000;
+
+/// a 16550 compatible UART
+pub struct Uart {
+    base: u16,
+}
+
+impl Uart {
+    /// creates a new UART with the given base I/O port. this doesn't touch the hardware
+    pub const fn new(base: u16) -> Self {
+        Self { base }
+    }
+
+    /// sets up the UART with the given baud rate and 8N1 framing, with interrupts disabled.
+    /// returns false if the baud rate is 0 or the UART didn't pass a loopback test, which usually means it isn't present
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    pub unsafe fn init(&self, baud_rate: u32) -> bool {
+        let Some(divisor) = BASE_BAUD_RATE.checked_div(baud_rate) else {
+            return false;
+        };
+        let divisor = divisor.clamp(1, u16::MAX.into()) as u16;
+        let [divisor_low, divisor_high] = divisor.to_le_bytes();
+
+        outb(self.base + REG_INTERRUPT_ENABLE, 0);
+        outb(self.base + REG_LINE_CONTROL, LINE_CONTROL_DLAB);
+        outb(self.base + REG_DATA, divisor_low);
+        outb(self.base + REG_INTERRUPT_ENABLE, divisor_high);
+        outb(self.base + REG_LINE_CONTROL, LINE_CONTROL_8N1);
+        outb(self.base + REG_FIFO_CONTROL, FIFO_CONTROL_ENABLE);
+
+        outb(self.base + REG_MODEM_CONTROL, MODEM_CONTROL_LOOPBACK);
+        outb(self.base + REG_DATA, LOOPBACK_TEST_BYTE);
+        let is_present = inb(self.base + REG_DATA) == LOOPBACK_TEST_BYTE;
+
+        // leave loopback mode even if the test failed, otherwise anything written afterwards would never make it out
+        outb(self.base + REG_MODEM_CONTROL, MODEM_CONTROL_NORMAL);
+        is_present
+    }
+
+    /// writes a byte to the UART, waiting for space in the transmit FIFO if needed.
+    /// returns false if there was never any space, in which case the byte is dropped
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    pub unsafe fn write_byte(&self, byte: u8) -> bool {
+        if (0..POLL_ATTEMPTS).any(|_| inb(self.base + REG_LINE_STATUS) & LINE_STATUS_TRANSMIT_EMPTY != 0) {
+            outb(self.base + REG_DATA, byte);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// reads a byte from the UART if one has been received
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does port accesses without synchronisation
+    pub unsafe fn read_byte(&self) -> Option<u8> {
+        if inb(self.base + REG_LINE_STATUS) & LINE_STATUS_DATA_READY == 0 {
+            None
+        } else {
+            Some(inb(self.base + REG_DATA))
+        }
+    }
+}
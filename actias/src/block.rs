@@ -0,0 +1,36 @@
+use actias_api::Errno;
+
+/// a device that can be read from and written to in fixed-size blocks
+pub trait BlockDevice {
+    /// the size of a block on this device in bytes
+    fn block_size(&self) -> usize;
+
+    /// the total number of blocks on this device
+    fn num_blocks(&self) -> u64;
+
+    /// reads blocks starting at the given block number into the buffer, whose length must be a multiple of the block size
+    fn read_blocks(&self, start: u64, buf: &mut [u8]) -> Result<(), Errno>;
+
+    /// writes blocks starting at the given block number from the buffer, whose length must be a multiple of the block size
+    fn write_blocks(&self, start: u64, buf: &[u8]) -> Result<(), Errno>;
+
+    /// the total size of this device in bytes
+    fn size(&self) -> u64 {
+        self.num_blocks() * self.block_size() as u64
+    }
+}
+
+/// checks that a transfer of the given buffer length starting at the given block is within the bounds of a device and is a whole number of blocks,
+/// returning the number of blocks it covers
+pub fn check_transfer<D: BlockDevice + ?Sized>(device: &D, start: u64, len: usize) -> Result<u64, Errno> {
+    let block_size = device.block_size();
+    if len % block_size != 0 {
+        return Err(Errno::InvalidArgument);
+    }
+
+    let count = (len / block_size) as u64;
+    match start.checked_add(count) {
+        Some(end) if end <= device.num_blocks() => Ok(count),
+        _ => Err(Errno::InvalidArgument),
+    }
+}
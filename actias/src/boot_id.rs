@@ -0,0 +1,53 @@
+use core::fmt;
+use spin::Once;
+
+/// a random UUID that's unique to each boot. the default is the nil UUID
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BootId([u8; 16]);
+
+impl BootId {
+    /// creates a new version 4 (random) UUID from the given source of random numbers, returning None if it runs out
+    pub fn new<F: FnMut() -> Option<u32>>(mut random: F) -> Option<Self> {
+        let mut bytes = [0; 16];
+        for chunk in bytes.chunks_exact_mut(4) {
+            chunk.copy_from_slice(&random()?.to_le_bytes());
+        }
+
+        // set the version and variant fields
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+        Some(Self(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl fmt::Display for BootId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                write!(f, "-")?;
+            }
+            write!(f, "{byte:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// the ID of the current boot, generated once at startup
+static BOOT_ID: Once<BootId> = Once::new();
+
+/// generates the boot ID from the given source of random numbers, returning None if it runs out.
+/// once an ID has been generated, later calls return it without using the source
+pub fn init<F: FnMut() -> Option<u32>>(random: F) -> Option<&'static BootId> {
+    BOOT_ID.try_call_once(|| BootId::new(random).ok_or(())).ok()
+}
+
+/// gets the ID of the current boot, if it's been generated
+pub fn get() -> Option<&'static BootId> {
+    BOOT_ID.get()
+}
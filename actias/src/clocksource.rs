@@ -0,0 +1,81 @@
+use spin::RwLock;
+
+/// a free-running counter that can be used to measure the passage of time
+pub trait ClockSource: Sync {
+    /// a short name for this clock source, like "tsc"
+    fn name(&self) -> &'static str;
+
+    /// how good this clock source is compared to others, where higher is better. sources that aren't stable enough to keep time with should return 0
+    fn rating(&self) -> u32;
+
+    /// how many times the counter increments per second
+    fn frequency(&self) -> u64;
+
+    /// reads the counter. this must never go backwards
+    fn read(&self) -> u64;
+
+    /// reads the counter without blocking, returning None if it can't be read right now.
+    /// this is used where blocking could deadlock, like while logging
+    fn try_read(&self) -> Option<u64> {
+        Some(self.read())
+    }
+
+    /// converts a counter value to nanoseconds
+    fn to_nanoseconds(&self, count: u64) -> u64 {
+        (u128::from(count) * 1_000_000_000 / u128::from(self.frequency().max(1))) as u64
+    }
+
+    /// reads the counter and converts it to nanoseconds
+    fn nanoseconds(&self) -> u64 {
+        self.to_nanoseconds(self.read())
+    }
+}
+
+/// the clock source currently used for timekeeping
+struct Current {
+    source: &'static dyn ClockSource,
+
+    /// added to the source's time, so the clock carries on from where the previous source left off
+    offset: u64,
+}
+
+impl Current {
+    fn nanoseconds(&self) -> u64 {
+        self.source.nanoseconds() + self.offset
+    }
+}
+
+static CURRENT: RwLock<Option<Current>> = RwLock::new(None);
+
+/// picks the highest rated of the given clock sources and makes it the current one, returning it.
+/// sources with a rating of 0 are never picked
+pub fn select<I: IntoIterator<Item = &'static dyn ClockSource>>(sources: I) -> Option<&'static dyn ClockSource> {
+    let best = sources.into_iter().filter(|source| source.rating() > 0).max_by_key(|source| source.rating())?;
+    set_current(best);
+    Some(best)
+}
+
+/// switches to the given clock source. the monotonic clock never goes backwards when switching, even if the new source's counter is behind
+pub fn set_current(source: &'static dyn ClockSource) {
+    let mut current = CURRENT.write();
+    let now = current.as_ref().map_or(0, Current::nanoseconds);
+    let offset = now.saturating_sub(source.nanoseconds());
+    *current = Some(Current { source, offset });
+}
+
+/// gets the current clock source, if one has been selected
+pub fn current() -> Option<&'static dyn ClockSource> {
+    CURRENT.read().as_ref().map(|current| current.source)
+}
+
+/// gets the current value of the monotonic clock in nanoseconds, if a clock source has been selected
+pub fn nanoseconds() -> Option<u64> {
+    CURRENT.read().as_ref().map(Current::nanoseconds)
+}
+
+/// like nanoseconds(), but returns None instead of blocking if the clock source is being switched or is busy
+pub fn try_nanoseconds() -> Option<u64> {
+    let current = CURRENT.try_read()?;
+    let current = current.as_ref()?;
+    current.source.try_read().map(|count| current.source.to_nanoseconds(count) + current.offset)
+}
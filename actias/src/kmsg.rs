@@ -0,0 +1,105 @@
+use core::fmt;
+use spin::Mutex;
+
+/// size of the kernel log buffer in bytes
+pub const KMSG_SIZE: usize = 0x4000;
+
+/// buffer holding the most recent kernel log output, which every logger writes into
+pub static KMSG: Mutex<RingBuffer<KMSG_SIZE>> = Mutex::new(RingBuffer::new());
+
+/// a fixed-size buffer of text that overwrites the oldest data when it fills up.
+/// data is addressed by its position in the stream of everything ever written, so readers can tell when they've fallen behind
+pub struct RingBuffer<const N: usize> {
+    data: [u8; N],
+    written: u64,
+}
+
+impl<const N: usize> RingBuffer<N> {
+    pub const fn new() -> Self {
+        Self { data: [0; N], written: 0 }
+    }
+
+    /// the position of the oldest data still in the buffer
+    pub fn start(&self) -> u64 {
+        self.written.saturating_sub(N as u64)
+    }
+
+    /// the position just past the newest data in the buffer
+    pub fn end(&self) -> u64 {
+        self.written
+    }
+
+    fn byte_at(&self, position: u64) -> u8 {
+        self.data[(position % N as u64) as usize]
+    }
+
+    /// adds data to the buffer, overwriting the oldest data if it's full
+    pub fn push(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.data[(self.written % N as u64) as usize] = byte;
+            self.written += 1;
+        }
+    }
+
+    /// reads data starting at the given position into the buffer, returning how many bytes were read and the position to continue reading from.
+    /// if the position has already been overwritten, reading skips ahead to the start of the oldest complete line
+    pub fn read(&self, mut position: u64, buf: &mut [u8]) -> (usize, u64) {
+        if position < self.start() {
+            position = self.start();
+            while position < self.end() && self.byte_at(position) != b'\n' {
+                position += 1;
+            }
+            position = (position + 1).min(self.end());
+        }
+
+        let mut len = 0;
+        for dest in buf.iter_mut() {
+            if position >= self.end() {
+                break;
+            }
+            *dest = self.byte_at(position);
+            position += 1;
+            len += 1;
+        }
+
+        (len, position)
+    }
+}
+
+impl<const N: usize> Default for RingBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> fmt::Write for RingBuffer<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// reads the kernel log sequentially, like dmesg does
+pub struct KmsgReader {
+    position: u64,
+}
+
+impl KmsgReader {
+    /// creates a reader starting at the oldest complete line in the kernel log
+    pub fn new() -> Self {
+        Self { position: 0 }
+    }
+
+    /// reads as much of the kernel log as will fit into the buffer, returning the number of bytes read. returns 0 once caught up
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        let (len, position) = KMSG.lock().read(self.position, buf);
+        self.position = position;
+        len
+    }
+}
+
+impl Default for KmsgReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
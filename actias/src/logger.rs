@@ -1,9 +1,55 @@
-use core::fmt::Write;
+use crate::{clocksource, kmsg::KMSG};
+use core::{
+    fmt::Write,
+    hint::spin_loop,
+    sync::atomic::{AtomicBool, Ordering},
+};
 use log::{LevelFilter, Log, Metadata, Record};
-use spin::Mutex;
+use spin::{Mutex, MutexGuard};
 
-/// simple logger implementation
+/// how many times to try locking a writer while panicking before assuming whoever holds it won't release it
+const PANIC_LOCK_ATTEMPTS: usize = 0x10_0000;
+
+/// whether the kernel is panicking, in which case locks are broken if needed to make sure the panic message gets out
+static PANICKING: AtomicBool = AtomicBool::new(false);
+
+/// switches all loggers into panic mode, where a writer lock that can't be acquired in a reasonable amount of time is forcibly broken
+/// instead of the message being dropped
+pub fn enter_panic_mode() {
+    PANICKING.store(true, Ordering::SeqCst);
+}
+
+/// writes a log record in the format used for all log output
+fn write_record<W: Write>(writer: &mut W, record: &Record) {
+    let level = record.level();
+    let width = 5;
+    let target = record.target();
+    let args = record.args();
+
+    let _ = write!(writer, "{level:width$} ");
+    if let Some(path) = record.module_path() {
+        if target != path {
+            let _ = write!(writer, "({target}) ");
+        }
+        let _ = write!(writer, "[{path}] ");
+    } else {
+        let _ = write!(writer, "[?] ({target}) ");
+    }
+    let _ = writeln!(writer, "{args}");
+}
+
+/// writes a timestamp in seconds since the clock source started, like dmesg does. this is 0 until a clock source has been selected,
+/// or if it couldn't be read without blocking
+fn write_timestamp<W: Write>(writer: &mut W, nanoseconds: u64) {
+    let seconds = nanoseconds / 1_000_000_000;
+    let microseconds = nanoseconds % 1_000_000_000 / 1000;
+
+    let _ = write!(writer, "[{seconds:5}.{microseconds:06}] ");
+}
+
+/// simple logger implementation. everything logged is also kept in the kernel log buffer
 pub struct Logger<T: Send + Write> {
+    /// the level to log at when the logger is installed. it can be changed at runtime with log::set_max_level()
     pub max_level: LevelFilter,
     pub writer: Mutex<T>,
 }
@@ -15,11 +61,36 @@ impl<T: Send + Write> Logger<T> {
             writer: Mutex::new(writer),
         }
     }
+
+    /// locks the writer, or returns None if it's in use and the kernel isn't panicking
+    fn lock_writer(&self) -> Option<MutexGuard<'_, T>> {
+        if let Some(writer) = self.writer.try_lock() {
+            return Some(writer);
+        }
+
+        if !PANICKING.load(Ordering::SeqCst) {
+            // if the lock can't be acquired, just return- it's probably fine
+            return None;
+        }
+
+        for _ in 0..PANIC_LOCK_ATTEMPTS {
+            if let Some(writer) = self.writer.try_lock() {
+                return Some(writer);
+            }
+            spin_loop();
+        }
+
+        // whatever was holding the lock is never going to release it, and the panic message is more important than what it was writing
+        unsafe {
+            self.writer.force_unlock();
+        }
+        self.writer.try_lock()
+    }
 }
 
 impl<T: Send + Write> Log for Logger<T> {
     fn enabled(&self, metadata: &Metadata) -> bool {
-        metadata.level() <= self.max_level
+        metadata.level() <= log::max_level()
     }
 
     fn log(&self, record: &Record) {
@@ -27,26 +98,19 @@ impl<T: Send + Write> Log for Logger<T> {
             return;
         }
 
-        let level = record.level();
-        let width = 5;
-        let target = record.target();
-        let args = record.args();
+        // keep a copy in the kernel log buffer even if the output device is busy
+        // this can't block, since whatever's logging might be interrupting the clock source or panicking while it's held
+        let time = clocksource::try_nanoseconds().unwrap_or_default();
+        if let Some(mut kmsg) = KMSG.try_lock() {
+            write_timestamp(&mut *kmsg, time);
+            write_record(&mut *kmsg, record);
+        }
 
-        let Some(mut writer) = self.writer.try_lock() else {
-            // if the lock can't be acquired, just return- it's probably fine
+        let Some(mut writer) = self.lock_writer() else {
             return;
         };
 
-        let _ = write!(&mut writer, "{level:width$} ");
-        if let Some(path) = record.module_path() {
-            if target != path {
-                let _ = write!(&mut writer, "({target}) ");
-            }
-            let _ = write!(&mut writer, "[{path}] ");
-        } else {
-            let _ = write!(&mut writer, "[?] ({target}) ");
-        }
-        let _ = writeln!(&mut writer, "{args}");
+        write_record(&mut *writer, record);
     }
 
     fn flush(&self) {}
@@ -19,22 +19,32 @@
 
 pub mod logger;
 pub mod arch;
+pub mod block;
+pub mod clocksource;
+pub mod kmsg;
+pub mod boot_id;
+pub mod time;
 
 use log::error;
 
 #[panic_handler]
 pub fn panic_implementation(info: &core::panic::PanicInfo) -> ! {
+    logger::enter_panic_mode();
+
     let (file, line) = match info.location() {
         Some(loc) => (loc.file(), loc.line()),
         None => ("", 0),
     };
 
+    // tag the panic with the boot it happened in, so it can be matched up with other logs. this is the nil UUID if there's no boot ID
+    let boot_id = boot_id::get().copied().unwrap_or_default();
+
     if let Some(m) = info.message() {
-        error!("PANIC: {m} at {file}:{line}");
+        error!("PANIC: {m} at {file}:{line} (boot {boot_id})");
     } else if let Some(m) = info.payload().downcast_ref::<&str>() {
-        error!("PANIC: {m} at {file}:{line}");
+        error!("PANIC: {m} at {file}:{line} (boot {boot_id})");
     } else {
-        error!("PANIC (no message) at {file}:{line}");
+        error!("PANIC (no message) at {file}:{line} (boot {boot_id})");
     }
 
     loop {}
@@ -0,0 +1,34 @@
+use crate::clocksource;
+use spin::Once;
+
+pub use actias_api::time::{ClockId, DateTime};
+
+/// the wall-clock time at boot in nanoseconds since the unix epoch, and the monotonic clock's value when it was read
+static BOOT_TIME: Once<(i64, Option<u64>)> = Once::new();
+
+/// records the wall-clock time read at boot, so realtime() can count forward from it. this only has an effect the first time it's called
+pub fn init(now: DateTime) {
+    BOOT_TIME.call_once(|| (now.to_unix_timestamp() * 1_000_000_000, monotonic()));
+}
+
+/// gets the time since boot in nanoseconds, which never goes backwards. returns None if there's no clock source that can keep time
+pub fn monotonic() -> Option<u64> {
+    clocksource::nanoseconds()
+}
+
+/// gets the wall-clock time in nanoseconds since the unix epoch by counting forward from the time read at boot.
+/// returns None if the time wasn't read at boot or there was no clock source to count with
+pub fn realtime() -> Option<i64> {
+    let (boot_time, boot_monotonic) = *BOOT_TIME.get()?;
+    let elapsed = monotonic()?.saturating_sub(boot_monotonic?);
+
+    Some(boot_time + elapsed as i64)
+}
+
+/// reads the given clock in nanoseconds
+pub fn read(clock: ClockId) -> Option<i64> {
+    match clock {
+        ClockId::Realtime => realtime(),
+        ClockId::Monotonic => monotonic().map(|time| time as i64),
+    }
+}